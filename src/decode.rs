@@ -0,0 +1,308 @@
+use crate::JSONValue;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// An error produced while decoding a [`JSONValue`] into a typed Rust value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// Expected the first type, found the second.
+    ExpectedType(&'static str, &'static str),
+    MissingField(String),
+    IntegerOutOfRange(f64),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::ExpectedType(want, got) => {
+                write!(f, "expected {}, found {}", want, got)
+            }
+            DecodeError::MissingField(name) => write!(f, "missing field \"{}\"", name),
+            DecodeError::IntegerOutOfRange(n) => write!(f, "{} has no integer representation", n),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn type_name(value: &JSONValue) -> &'static str {
+    match value {
+        JSONValue::Number(_) => "number",
+        JSONValue::String(_) => "string",
+        JSONValue::Bool(_) => "bool",
+        JSONValue::Array(_) => "array",
+        JSONValue::Object(_) => "object",
+        JSONValue::Null => "null",
+    }
+}
+
+/// Walks a [`JSONValue`] tree to reconstruct typed Rust values, in the
+/// style of rustc's old `libserialize::json::Decoder`.
+pub struct Decoder<'a> {
+    value: &'a JSONValue,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(value: &'a JSONValue) -> Self {
+        Self { value }
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64, DecodeError> {
+        match self.value {
+            JSONValue::Number(n) => Ok(*n),
+            v => Err(DecodeError::ExpectedType("number", type_name(v))),
+        }
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, DecodeError> {
+        let n = self.read_f64()?;
+        if n.fract() != 0.0 || n < 0.0 || n > u64::MAX as f64 {
+            return Err(DecodeError::IntegerOutOfRange(n));
+        }
+        Ok(n as u64)
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, DecodeError> {
+        let n = self.read_f64()?;
+        if n.fract() != 0.0 || n < i64::MIN as f64 || n > i64::MAX as f64 {
+            return Err(DecodeError::IntegerOutOfRange(n));
+        }
+        Ok(n as i64)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        let n = self.read_i64()?;
+        u32::try_from(n).map_err(|_| DecodeError::IntegerOutOfRange(n as f64))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, DecodeError> {
+        let n = self.read_i64()?;
+        i32::try_from(n).map_err(|_| DecodeError::IntegerOutOfRange(n as f64))
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        match self.value {
+            JSONValue::Bool(b) => Ok(*b),
+            v => Err(DecodeError::ExpectedType("bool", type_name(v))),
+        }
+    }
+
+    pub fn read_str(&mut self) -> Result<String, DecodeError> {
+        match self.value {
+            JSONValue::String(s) => Ok(s.clone()),
+            v => Err(DecodeError::ExpectedType("string", type_name(v))),
+        }
+    }
+
+    /// Calls `f` with whether the wrapped value is present (`false` for
+    /// `null`); `f` then decodes the `Some` payload from the same decoder
+    /// when it is.
+    pub fn read_option<T>(
+        &mut self,
+        f: impl FnOnce(&mut Decoder<'a>, bool) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        match self.value {
+            JSONValue::Null => f(self, false),
+            _ => f(self, true),
+        }
+    }
+
+    pub fn read_seq<T>(
+        &mut self,
+        f: impl FnOnce(&mut Decoder<'a>, usize) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        match self.value {
+            JSONValue::Array(arr) => f(self, arr.len()),
+            v => Err(DecodeError::ExpectedType("array", type_name(v))),
+        }
+    }
+
+    pub fn read_seq_elt<T>(
+        &mut self,
+        idx: usize,
+        f: impl FnOnce(&mut Decoder) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        match self.value {
+            JSONValue::Array(arr) => {
+                let item = arr
+                    .get(idx)
+                    .ok_or(DecodeError::ExpectedType("array element", "nothing"))?;
+                f(&mut Decoder::new(item))
+            }
+            v => Err(DecodeError::ExpectedType("array", type_name(v))),
+        }
+    }
+
+    pub fn read_map<T>(
+        &mut self,
+        f: impl FnOnce(&mut Decoder<'a>, usize) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        match self.value {
+            JSONValue::Object(map) => f(self, map.len()),
+            v => Err(DecodeError::ExpectedType("object", type_name(v))),
+        }
+    }
+
+    /// Looks up `name` as a struct field, erroring if it is absent.
+    pub fn read_struct_field<T>(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut Decoder) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        match self.value {
+            JSONValue::Object(map) => {
+                let field = map
+                    .get(name)
+                    .ok_or_else(|| DecodeError::MissingField(name.to_string()))?;
+                f(&mut Decoder::new(field))
+            }
+            v => Err(DecodeError::ExpectedType("object", type_name(v))),
+        }
+    }
+}
+
+/// A type that can be reconstructed from a [`JSONValue`] tree via a
+/// [`Decoder`]. Implemented by hand for the types below; struct
+/// implementations drive `read_struct_field` for each of their fields.
+pub trait Decodable: Sized {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError>;
+}
+
+impl Decodable for f64 {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_f64()
+    }
+}
+
+impl Decodable for u64 {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_u64()
+    }
+}
+
+impl Decodable for i64 {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_i64()
+    }
+}
+
+impl Decodable for u32 {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_u32()
+    }
+}
+
+impl Decodable for i32 {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_i32()
+    }
+}
+
+impl Decodable for bool {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_bool()
+    }
+}
+
+impl Decodable for String {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_str()
+    }
+}
+
+impl<T: Decodable> Decodable for Option<T> {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_option(|d, has_value| {
+            if has_value {
+                Ok(Some(T::decode(d)?))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+}
+
+impl<T: Decodable> Decodable for Vec<T> {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        d.read_seq(|d, len| {
+            let mut out = Vec::with_capacity(len);
+            for i in 0..len {
+                out.push(d.read_seq_elt(i, T::decode)?);
+            }
+            Ok(out)
+        })
+    }
+}
+
+impl<T: Decodable> Decodable for BTreeMap<String, T> {
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        match d.value {
+            JSONValue::Object(map) => {
+                let mut out = BTreeMap::new();
+                for (key, value) in map.iter() {
+                    out.insert(key.clone(), T::decode(&mut Decoder::new(value))?);
+                }
+                Ok(out)
+            }
+            v => Err(DecodeError::ExpectedType("object", type_name(v))),
+        }
+    }
+}
+
+/// Decodes `value` into a `T`, the entry point to the [`Decodable`] machinery.
+pub fn decode<T: Decodable>(value: &JSONValue) -> Result<T, DecodeError> {
+    T::decode(&mut Decoder::new(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrong_type_reports_expected_and_found() {
+        let err = decode::<f64>(&JSONValue::String("x".to_string())).unwrap_err();
+        assert_eq!(err, DecodeError::ExpectedType("number", "string"));
+    }
+
+    #[test]
+    fn fractional_number_is_out_of_range_for_an_integer() {
+        let err = decode::<i64>(&JSONValue::Number(1.5)).unwrap_err();
+        assert_eq!(err, DecodeError::IntegerOutOfRange(1.5));
+    }
+
+    #[test]
+    fn negative_number_is_out_of_range_for_u64() {
+        let err = decode::<u64>(&JSONValue::Number(-1.0)).unwrap_err();
+        assert_eq!(err, DecodeError::IntegerOutOfRange(-1.0));
+    }
+
+    #[test]
+    fn value_too_large_for_u32_is_out_of_range() {
+        let err = decode::<u32>(&JSONValue::Number(u32::MAX as f64 + 1.0)).unwrap_err();
+        assert!(matches!(err, DecodeError::IntegerOutOfRange(_)));
+    }
+
+    #[test]
+    fn missing_struct_field_is_reported_by_name() {
+        let obj = JSONValue::Object(BTreeMap::new());
+        let err = Decoder::new(&obj)
+            .read_struct_field("name", |d| d.read_str())
+            .unwrap_err();
+        assert_eq!(err, DecodeError::MissingField("name".to_string()));
+    }
+
+    #[test]
+    fn array_index_past_the_end_is_reported() {
+        let arr = JSONValue::Array(vec![JSONValue::Number(1.0)]);
+        let err = Decoder::new(&arr)
+            .read_seq_elt(5, |d| d.read_f64())
+            .unwrap_err();
+        assert_eq!(err, DecodeError::ExpectedType("array element", "nothing"));
+    }
+
+    #[test]
+    fn decodes_a_vec_of_numbers() {
+        let arr = JSONValue::Array(vec![JSONValue::Number(1.0), JSONValue::Number(2.0)]);
+        assert_eq!(decode::<Vec<f64>>(&arr).unwrap(), vec![1.0, 2.0]);
+    }
+}
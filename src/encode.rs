@@ -0,0 +1,245 @@
+use crate::JSONValue;
+use std::{fmt, io};
+
+/// Indentation settings for [`Encoder::pretty`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrettyConfig {
+    pub indent_width: usize,
+    pub indent_char: char,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: 2,
+            indent_char: ' ',
+        }
+    }
+}
+
+/// Writes a [`JSONValue`] tree to any [`fmt::Write`], either as a single
+/// compact line or with configurable indentation, escaping strings as it
+/// goes rather than building up an intermediate `String`.
+pub struct Encoder<W: fmt::Write> {
+    writer: W,
+    pretty: Option<PrettyConfig>,
+    depth: usize,
+}
+
+impl<W: fmt::Write> Encoder<W> {
+    /// The canonical, whitespace-free form suitable for round-tripping.
+    pub fn compact(writer: W) -> Self {
+        Self {
+            writer,
+            pretty: None,
+            depth: 0,
+        }
+    }
+
+    pub fn pretty(writer: W, config: PrettyConfig) -> Self {
+        Self {
+            writer,
+            pretty: Some(config),
+            depth: 0,
+        }
+    }
+
+    pub fn encode(&mut self, value: &JSONValue) -> fmt::Result {
+        match value {
+            JSONValue::Null => self.writer.write_str("null"),
+            JSONValue::Bool(b) => write!(self.writer, "{}", b),
+            JSONValue::Number(n) => write!(self.writer, "{}", n),
+            JSONValue::String(s) => self.write_escaped(s),
+            JSONValue::Array(arr) => {
+                if arr.is_empty() {
+                    return self.writer.write_str("[]");
+                }
+                self.writer.write_char('[')?;
+                self.depth += 1;
+                for (i, item) in arr.iter().enumerate() {
+                    if i > 0 {
+                        self.writer.write_char(',')?;
+                    }
+                    self.write_newline_indent()?;
+                    self.encode(item)?;
+                }
+                self.depth -= 1;
+                self.write_newline_indent()?;
+                self.writer.write_char(']')
+            }
+            JSONValue::Object(map) => {
+                if map.is_empty() {
+                    return self.writer.write_str("{}");
+                }
+                self.writer.write_char('{')?;
+                self.depth += 1;
+                for (i, (key, val)) in map.iter().enumerate() {
+                    if i > 0 {
+                        self.writer.write_char(',')?;
+                    }
+                    self.write_newline_indent()?;
+                    self.write_escaped(key)?;
+                    self.writer
+                        .write_str(if self.pretty.is_some() { ": " } else { ":" })?;
+                    self.encode(val)?;
+                }
+                self.depth -= 1;
+                self.write_newline_indent()?;
+                self.writer.write_char('}')
+            }
+        }
+    }
+
+    fn write_newline_indent(&mut self) -> fmt::Result {
+        let Some(cfg) = self.pretty else { return Ok(()) };
+        self.writer.write_char('\n')?;
+        for _ in 0..self.depth * cfg.indent_width {
+            self.writer.write_char(cfg.indent_char)?;
+        }
+        Ok(())
+    }
+
+    fn write_escaped(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_char('"')?;
+        for c in s.chars() {
+            match c {
+                '"' => self.writer.write_str("\\\"")?,
+                '\\' => self.writer.write_str("\\\\")?,
+                '\n' => self.writer.write_str("\\n")?,
+                '\r' => self.writer.write_str("\\r")?,
+                '\t' => self.writer.write_str("\\t")?,
+                '\u{8}' => self.writer.write_str("\\b")?,
+                '\u{c}' => self.writer.write_str("\\f")?,
+                c if (c as u32) < 0x20 => write!(self.writer, "\\u{:04x}", c as u32)?,
+                c => self.writer.write_char(c)?,
+            }
+        }
+        self.writer.write_char('"')
+    }
+}
+
+pub fn to_string_compact(value: &JSONValue) -> String {
+    let mut out = String::new();
+    Encoder::compact(&mut out)
+        .encode(value)
+        .expect("writing to a String cannot fail");
+    out
+}
+
+pub fn to_writer_pretty<W: fmt::Write>(
+    writer: &mut W,
+    value: &JSONValue,
+    indent_width: usize,
+) -> fmt::Result {
+    Encoder::pretty(
+        writer,
+        PrettyConfig {
+            indent_width,
+            ..PrettyConfig::default()
+        },
+    )
+    .encode(value)
+}
+
+/// Adapts an [`io::Write`] sink (a `File`, a socket, ...) into the
+/// [`fmt::Write`] the [`Encoder`] expects, so large trees can be streamed
+/// straight out instead of first collecting into a `String`. `fmt::Write`
+/// can't carry an `io::Error`, so the original error is stashed here and
+/// recovered by the `to_io_writer_*` functions below.
+struct IoWriteAdapter<'a, W: io::Write> {
+    writer: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: io::Write> fmt::Write for IoWriteAdapter<'a, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+fn encode_to_io_writer<W: io::Write>(
+    writer: &mut W,
+    value: &JSONValue,
+    pretty: Option<PrettyConfig>,
+) -> io::Result<()> {
+    let mut adapter = IoWriteAdapter { writer, error: None };
+    let encoded = match pretty {
+        Some(config) => Encoder::pretty(&mut adapter, config).encode(value),
+        None => Encoder::compact(&mut adapter).encode(value),
+    };
+    match encoded {
+        Ok(()) => Ok(()),
+        Err(_) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatting error"))),
+    }
+}
+
+/// The canonical, whitespace-free form, written straight to `writer`.
+pub fn to_io_writer_compact<W: io::Write>(writer: &mut W, value: &JSONValue) -> io::Result<()> {
+    encode_to_io_writer(writer, value, None)
+}
+
+/// Indented with `indent_width` spaces per nesting level, written straight
+/// to `writer`.
+pub fn to_io_writer_pretty<W: io::Write>(
+    writer: &mut W,
+    value: &JSONValue,
+    indent_width: usize,
+) -> io::Result<()> {
+    encode_to_io_writer(
+        writer,
+        value,
+        Some(PrettyConfig {
+            indent_width,
+            ..PrettyConfig::default()
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn escapes_quotes_backslashes_and_control_chars() {
+        let v = JSONValue::String("a\"b\\c\nd\u{1}e".to_string());
+        assert_eq!(to_string_compact(&v), r#""a\"b\\c\nd\u0001e""#);
+    }
+
+    #[test]
+    fn compact_has_no_whitespace() {
+        let v = JSONValue::Array(vec![JSONValue::Number(1.0), JSONValue::Number(2.0)]);
+        assert_eq!(to_string_compact(&v), "[1,2]");
+    }
+
+    #[test]
+    fn pretty_indents_nested_values() {
+        let mut map = BTreeMap::new();
+        map.insert("a".to_string(), JSONValue::Number(1.0));
+        let v = JSONValue::Object(map);
+        let mut out = String::new();
+        to_writer_pretty(&mut out, &v, 2).unwrap();
+        assert_eq!(out, "{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn empty_containers_stay_on_one_line_even_when_pretty() {
+        let v = JSONValue::Array(vec![]);
+        let mut out = String::new();
+        to_writer_pretty(&mut out, &v, 2).unwrap();
+        assert_eq!(out, "[]");
+    }
+
+    #[test]
+    fn io_writer_round_trips_through_a_byte_sink() {
+        let v = JSONValue::Array(vec![JSONValue::Number(1.0), JSONValue::Number(2.0)]);
+        let mut buf = Vec::new();
+        to_io_writer_compact(&mut buf, &v).unwrap();
+        assert_eq!(buf, b"[1,2]");
+    }
+}
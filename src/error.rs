@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// The specific reason a `SyntaxError` was raised, independent of where in
+/// the input it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    EOFWhileParsingList,
+    EOFWhileParsingObject,
+    EOFWhileParsingString,
+    EOFWhileParsingValue,
+    ExpectedColon,
+    ExpectedListCommaOrEnd,
+    ExpectedObjectCommaOrEnd,
+    ExpectedSomeValue,
+    InvalidEscape,
+    InvalidUnicodeCodePoint,
+    KeyMustBeAString,
+    LoneLeadingSurrogateInHexEscape,
+    TrailingCharacters,
+    UnexpectedEndOfHexEscape,
+    UnrecognizedHex,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            ErrorCode::EOFWhileParsingList => "EOF while parsing a list",
+            ErrorCode::EOFWhileParsingObject => "EOF while parsing an object",
+            ErrorCode::EOFWhileParsingString => "EOF while parsing a string",
+            ErrorCode::EOFWhileParsingValue => "EOF while parsing a value",
+            ErrorCode::ExpectedColon => "expected `:`",
+            ErrorCode::ExpectedListCommaOrEnd => "expected `,` or `]`",
+            ErrorCode::ExpectedObjectCommaOrEnd => "expected `,` or `}`",
+            ErrorCode::ExpectedSomeValue => "expected a value",
+            ErrorCode::InvalidEscape => "invalid escape",
+            ErrorCode::InvalidUnicodeCodePoint => "invalid unicode code point",
+            ErrorCode::KeyMustBeAString => "key must be a string",
+            ErrorCode::LoneLeadingSurrogateInHexEscape => {
+                "lone leading surrogate in hex escape"
+            }
+            ErrorCode::TrailingCharacters => "trailing characters",
+            ErrorCode::UnexpectedEndOfHexEscape => "unexpected end of hex escape",
+            ErrorCode::UnrecognizedHex => "invalid \\u escape (not hex)",
+        };
+        f.write_str(msg)
+    }
+}
+
+/// An error produced while parsing JSON text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParserError {
+    /// A malformed token was found at the given 1-based `(line, col)`.
+    SyntaxError(ErrorCode, usize, usize),
+    /// The input ended while a value was still being parsed.
+    EOFWhileParsing,
+    /// A numeric literal could not be parsed as an `f64`.
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParserError::SyntaxError(code, line, col) => {
+                write!(f, "{} at line {} column {}", code, line, col)
+            }
+            ParserError::EOFWhileParsing => write!(f, "unexpected end of input"),
+            ParserError::InvalidNumber(s) => write!(f, "invalid number literal \"{}\"", s),
+        }
+    }
+}
+
+impl std::error::Error for ParserError {}
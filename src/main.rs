@@ -1,96 +1,113 @@
-use regex::Regex;
+mod decode;
+mod encode;
+mod error;
+mod path;
+mod stream;
+
+use error::{ErrorCode, ParserError};
+use path::PathError;
+use stream::{JsonEvent, JsonEvents};
 use std::{
-    collections::{BTreeMap, LinkedList},
+    collections::BTreeMap,
     env, fmt, fs,
     time::Instant,
 };
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum JSONValue {
     Number(f64),
     String(String),
     Bool(bool),
-    Array(LinkedList<JSONValue>),
+    Array(Vec<JSONValue>),
     Object(BTreeMap<String, JSONValue>),
     Null,
 }
 
 impl JSONValue {
-    fn repr(&self, indent: usize) -> String {
-        let indstr = (0..=indent).map(|_| "  ").collect::<String>();
-        let r = match self {
-            JSONValue::Null => String::from("null"),
-            JSONValue::String(s) => {
-                let repr = format!("\"{}\"", s.as_str());
-                repr
-            }
-            JSONValue::Object(map) => {
-                if map.len() == 0 {
-                    return "{}".to_string();
-                }
-                let indstrend = (0..indent).map(|_| "  ").collect::<String>();
-                let repr = map
-                    .iter()
-                    .map(|(key, val)| {
-                        format!(
-                            "{}\"{}\": {}",
-                            indstr,
-                            key,
-                            val.repr(indent + 1)
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join(",\n");
-                format!("{{\n{}\n{}}}", repr, indstrend)
-            }
-            JSONValue::Array(arr) => {
-                if arr.len() == 0 {
-                    return "[]".to_string();
-                }
-                let indstrend = (0..indent).map(|_| "  ").collect::<String>();
-                let repr = arr
-                    .iter()
-                    .map(|val| format!("{}{}", indstr, val.repr(indent + 1)))
-                    .collect::<Vec<String>>()
-                    .join(",\n");
-                format!("[\n{}\n{}]", repr, indstrend)
-            }
-            JSONValue::Number(n) => n.to_string(),
-            JSONValue::Bool(n) => n.to_string(),
-        };
-        r
-    }
-
-    //     pub fn from_path(&self, path: &str) -> &Self {
-    //         let re = Regex::new(r".(\w+)|\[(\d+)\]").unwrap();
-    //         for c in re.captures_iter(path) {
-    //             println!("{:?}", c.get(1).unwrap().as_str());
-    //         }
-    //         match self {
-    //             JSONValue::Object(map) => self,
-    //             JSONValue::Array(arr) => self,
-    //             _ => self,
-    //         }
-    //     }
-
-    //     fn get_by_key(&self, path: &str) -> Option<&Self> {
-    //         match self {
-    //             JSONValue::Object(map) => Some(self),
-    //             JSONValue::Array(arr) => {
-    //                 if let Ok(n) = path.parse::<usize>() {
-    //                     Some(arr.iter().nth(n).unwrap())
-    //                 } else {
-    //                     None
-    //                 }
-    //             }
-    //             _ => None,
-    //         }
-    //     }
+    /// Evaluates a JSONPath expression against this value. See [`path::select`]
+    /// for the supported syntax.
+    pub fn select(&self, path: &str) -> Result<Vec<&JSONValue>, PathError> {
+        path::select(self, path)
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JSONValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JSONValue::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JSONValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JSONValue>> {
+        match self {
+            JSONValue::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// `O(1)` access into an `Array` by position; `None` for any other
+    /// variant or an out-of-bounds index.
+    pub fn get(&self, index: usize) -> Option<&JSONValue> {
+        match self {
+            JSONValue::Array(arr) => arr.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&BTreeMap<String, JSONValue>> {
+        match self {
+            JSONValue::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Decodes this value into a `T`. See [`decode::decode`].
+    pub fn decode<T: decode::Decodable>(&self) -> Result<T, decode::DecodeError> {
+        decode::decode(self)
+    }
+
+    /// Serializes to the canonical, whitespace-free one-line form.
+    pub fn to_string_compact(&self) -> String {
+        encode::to_string_compact(self)
+    }
+
+    /// Serializes with `indent_width` spaces of indentation per nesting
+    /// level, writing straight to `writer` instead of building a `String`.
+    pub fn to_writer_pretty<W: fmt::Write>(
+        &self,
+        writer: &mut W,
+        indent_width: usize,
+    ) -> fmt::Result {
+        encode::to_writer_pretty(writer, self, indent_width)
+    }
+}
+
+impl std::ops::Index<usize> for JSONValue {
+    type Output = JSONValue;
+
+    /// Panics if this isn't an `Array` or `index` is out of bounds, same as
+    /// `Vec`'s `Index` impl.
+    fn index(&self, index: usize) -> &JSONValue {
+        self.get(index).expect("index out of bounds")
+    }
 }
 
 impl fmt::Display for JSONValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let repr = self.repr(0);
-        write!(f, "{}", repr)
+        encode::Encoder::pretty(f, encode::PrettyConfig::default()).encode(self)
     }
 }
 
@@ -102,138 +119,346 @@ struct JSON<'a> {
     iter: std::str::Chars<'a>,
     cur_tok: char,
     pos: usize,
+    line: usize,
+    col: usize,
 }
 
 impl<'a> JSON<'a> {
     pub fn new(input: &'a str) -> Self {
         let mut iter = input.chars();
-        let cur_tok = iter.next().unwrap();
+        let mut cur_tok = iter.next().unwrap_or('\0');
+        let mut line = 1;
+        let mut col = 1;
+        while cur_tok.is_ascii_whitespace() {
+            if cur_tok == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+            cur_tok = iter.next().unwrap_or('\0');
+        }
         Self {
             iter,
             cur_tok,
             pos: 0,
+            line,
+            col,
         }
     }
 
-    fn next_tok(&mut self) -> char {
+    /// Advances to the next character without skipping whitespace. Used
+    /// inside string/escape parsing, where whitespace is significant
+    /// content rather than insignificant inter-token padding.
+    fn next_raw_tok(&mut self) -> char {
         let t = self.iter.next().unwrap_or('\0');
         self.pos += 1;
-        if t.is_ascii_whitespace() {
-            return self.next_tok();
+        if self.cur_tok == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
         }
         self.cur_tok = t;
         t
     }
 
-    fn expect(&mut self, ch: char) -> () {
+    /// Skips any run of insignificant whitespace `cur_tok` is currently on,
+    /// without first advancing past a non-whitespace character.
+    fn skip_ws(&mut self) {
+        while self.cur_tok.is_ascii_whitespace() {
+            self.next_raw_tok();
+        }
+    }
+
+    fn next_tok(&mut self) -> char {
+        self.next_raw_tok();
+        self.skip_ws();
+        self.cur_tok
+    }
+
+    fn error(&self, code: ErrorCode) -> ParserError {
+        ParserError::SyntaxError(code, self.line, self.col)
+    }
+
+    fn expect(&mut self, ch: char, code: ErrorCode) -> Result<(), ParserError> {
+        if self.cur_tok == '\0' {
+            return Err(ParserError::EOFWhileParsing);
+        }
         if self.cur_tok != ch {
-            format!("Unexpected token {}. Expected {}.", self.cur_tok, ch);
+            return Err(self.error(code));
         }
         self.next_tok();
+        Ok(())
     }
 
-    fn parse_any(&mut self) -> JSONValue {
+    fn parse_any(&mut self) -> Result<JSONValue, ParserError> {
         match self.cur_tok {
             '{' => self.parse_object(),
             '[' => self.parse_array(),
             't' | 'f' | 'n' => self.parse_literal(),
             '"' => self.parse_string(),
             '0'..='9' | '-' => self.parse_number(),
-            c => panic!("Unexpected token \"{}\" at start of JSON value.", c),
+            '\0' => Err(self.error(ErrorCode::EOFWhileParsingValue)),
+            _ => Err(self.error(ErrorCode::ExpectedSomeValue)),
         }
     }
 
-    fn parse_object(&mut self) -> JSONValue {
+    fn parse_object(&mut self) -> Result<JSONValue, ParserError> {
         let mut map = BTreeMap::new();
-        self.expect('{');
+        self.expect('{', ErrorCode::ExpectedSomeValue)?;
         if self.cur_tok == '}' {
             self.next_tok();
-            return JSONValue::Object(map);
+            return Ok(JSONValue::Object(map));
         }
         loop {
-            let key = match self.parse_string() {
+            if self.cur_tok != '"' {
+                return Err(self.error(ErrorCode::KeyMustBeAString));
+            }
+            let key = match self.parse_string()? {
                 JSONValue::String(s) => s,
-                _ => "".to_string(),
+                _ => unreachable!(),
             };
-            self.expect(':');
-            let value = self.parse_any();
+            self.expect(':', ErrorCode::ExpectedColon)?;
+            let value = self.parse_any()?;
             map.insert(key, value);
             let c = self.cur_tok;
             self.next_tok();
             match c {
                 ',' => continue,
                 '}' => break,
-                _ => panic!("Unexpected token \"{}\" in the end of object.", c),
+                '\0' => return Err(self.error(ErrorCode::EOFWhileParsingObject)),
+                _ => return Err(self.error(ErrorCode::ExpectedObjectCommaOrEnd)),
             }
         }
-        JSONValue::Object(map)
+        Ok(JSONValue::Object(map))
     }
 
-    fn parse_array(&mut self) -> JSONValue {
-        let mut arr = LinkedList::new();
-        self.expect('[');
+    fn parse_array(&mut self) -> Result<JSONValue, ParserError> {
+        let mut arr = Vec::new();
+        self.expect('[', ErrorCode::ExpectedSomeValue)?;
         if self.cur_tok == ']' {
             self.next_tok();
-            return JSONValue::Array(arr);
+            return Ok(JSONValue::Array(arr));
         }
         loop {
-            let value = self.parse_any();
-            arr.push_back(value);
+            let value = self.parse_any()?;
+            arr.push(value);
             let c = self.cur_tok;
             self.next_tok();
             match c {
                 ',' => continue,
                 ']' => break,
-                _ => panic!("Unexpected token \"{}\" in the end of array.", c),
+                '\0' => return Err(self.error(ErrorCode::EOFWhileParsingList)),
+                _ => return Err(self.error(ErrorCode::ExpectedListCommaOrEnd)),
+            }
+        }
+        Ok(JSONValue::Array(arr))
+    }
+
+    // Follows the RFC 8259 number grammar: `-? (0 | [1-9][0-9]*) ('.' [0-9]+)? ([eE] [+-]? [0-9]+)?`.
+    //
+    // Advances via `next_raw_tok` throughout, since `next_tok`'s
+    // whitespace-skipping would otherwise merge a number with a following
+    // token across intervening whitespace (e.g. `42 43` reading as `4243`).
+    // Trailing insignificant whitespace is skipped once at the end instead.
+    fn parse_number(&mut self) -> Result<JSONValue, ParserError> {
+        let mut s = String::new();
+        if self.cur_tok == '-' {
+            s.push('-');
+            self.next_raw_tok();
+        }
+        match self.cur_tok {
+            '0' => {
+                s.push('0');
+                self.next_raw_tok();
             }
+            '1'..='9' => {
+                while self.cur_tok.is_ascii_digit() {
+                    s.push(self.cur_tok);
+                    self.next_raw_tok();
+                }
+            }
+            _ => return Err(ParserError::InvalidNumber(s)),
+        }
+        if self.cur_tok == '.' {
+            s.push('.');
+            self.next_raw_tok();
+            if !self.cur_tok.is_ascii_digit() {
+                return Err(ParserError::InvalidNumber(s));
+            }
+            while self.cur_tok.is_ascii_digit() {
+                s.push(self.cur_tok);
+                self.next_raw_tok();
+            }
+        }
+        if self.cur_tok == 'e' || self.cur_tok == 'E' {
+            s.push(self.cur_tok);
+            self.next_raw_tok();
+            if self.cur_tok == '+' || self.cur_tok == '-' {
+                s.push(self.cur_tok);
+                self.next_raw_tok();
+            }
+            if !self.cur_tok.is_ascii_digit() {
+                return Err(ParserError::InvalidNumber(s));
+            }
+            while self.cur_tok.is_ascii_digit() {
+                s.push(self.cur_tok);
+                self.next_raw_tok();
+            }
+        }
+        self.skip_ws();
+        match s.parse::<f64>() {
+            // `f64::parse` saturates out-of-range literals like `1e400` to
+            // `inf` instead of erroring; the JSON grammar has no way to
+            // represent non-finite numbers, so reject them here.
+            Ok(n) if n.is_finite() => Ok(JSONValue::Number(n)),
+            _ => Err(ParserError::InvalidNumber(s)),
+        }
+    }
+
+    /// Reads the four hex digits of a `\uXXXX` escape (`cur_tok` is the `u`
+    /// on entry) and leaves `cur_tok` on the character following the escape.
+    fn parse_hex_escape(&mut self) -> Result<u32, ParserError> {
+        let mut n: u32 = 0;
+        for _ in 0..4 {
+            let c = self.next_raw_tok();
+            if c == '\0' {
+                return Err(self.error(ErrorCode::UnexpectedEndOfHexEscape));
+            }
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| self.error(ErrorCode::UnrecognizedHex))?;
+            n = n * 16 + digit;
         }
-        JSONValue::Array(arr)
+        self.next_raw_tok();
+        Ok(n)
     }
 
-    fn parse_number(&mut self) -> JSONValue {
-        let mut s = self.cur_tok.to_string();
+    fn parse_string(&mut self) -> Result<JSONValue, ParserError> {
+        self.next_raw_tok();
+        let mut s = String::new();
         loop {
-            match self.next_tok() {
-                t if t.is_ascii_digit() || t == '.' => {
-                    s.push(t);
+            match self.cur_tok {
+                '"' => {
+                    self.next_tok();
+                    return Ok(JSONValue::String(s));
+                }
+                '\0' => return Err(self.error(ErrorCode::EOFWhileParsingString)),
+                '\\' => {
+                    self.next_raw_tok();
+                    match self.cur_tok {
+                        '"' => {
+                            s.push('"');
+                            self.next_raw_tok();
+                        }
+                        '\\' => {
+                            s.push('\\');
+                            self.next_raw_tok();
+                        }
+                        '/' => {
+                            s.push('/');
+                            self.next_raw_tok();
+                        }
+                        'b' => {
+                            s.push('\u{8}');
+                            self.next_raw_tok();
+                        }
+                        'f' => {
+                            s.push('\u{c}');
+                            self.next_raw_tok();
+                        }
+                        'n' => {
+                            s.push('\n');
+                            self.next_raw_tok();
+                        }
+                        'r' => {
+                            s.push('\r');
+                            self.next_raw_tok();
+                        }
+                        't' => {
+                            s.push('\t');
+                            self.next_raw_tok();
+                        }
+                        'u' => {
+                            let hi = self.parse_hex_escape()?;
+                            let code = if (0xD800..=0xDBFF).contains(&hi) {
+                                if self.cur_tok != '\\' {
+                                    return Err(
+                                        self.error(ErrorCode::LoneLeadingSurrogateInHexEscape)
+                                    );
+                                }
+                                self.next_raw_tok();
+                                if self.cur_tok != 'u' {
+                                    return Err(
+                                        self.error(ErrorCode::LoneLeadingSurrogateInHexEscape)
+                                    );
+                                }
+                                let lo = self.parse_hex_escape()?;
+                                if !(0xDC00..=0xDFFF).contains(&lo) {
+                                    return Err(
+                                        self.error(ErrorCode::LoneLeadingSurrogateInHexEscape)
+                                    );
+                                }
+                                0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)
+                            } else {
+                                hi
+                            };
+                            let c = char::from_u32(code)
+                                .ok_or_else(|| self.error(ErrorCode::InvalidUnicodeCodePoint))?;
+                            s.push(c);
+                        }
+                        '\0' => return Err(self.error(ErrorCode::EOFWhileParsingString)),
+                        _ => return Err(self.error(ErrorCode::InvalidEscape)),
+                    }
+                }
+                c => {
+                    s.push(c);
+                    self.next_raw_tok();
                 }
-                _ => break,
             }
         }
-        JSONValue::Number(s.parse::<f64>().unwrap())
     }
 
-    fn parse_string(&mut self) -> JSONValue {
-        let striter = self.iter.by_ref().take_while(|&c| c != '"');
-        let s = striter.collect();
-        self.next_tok();
-        JSONValue::String(s)
+    /// Consumes `rest` (the literal's characters after the already-matched
+    /// first one) via `next_tok`, so `pos`/`line`/`col` stay in sync.
+    fn expect_literal_rest(&mut self, rest: &str) -> Result<(), ParserError> {
+        for ch in rest.chars() {
+            if self.cur_tok != ch {
+                return Err(self.error(ErrorCode::ExpectedSomeValue));
+            }
+            self.next_tok();
+        }
+        Ok(())
     }
 
-    fn parse_literal(&mut self) -> JSONValue {
-        let biter = self.iter.by_ref();
+    fn parse_literal(&mut self) -> Result<JSONValue, ParserError> {
         match self.cur_tok {
             't' => {
-                assert!(biter.take(TRUE.len()).eq(TRUE.chars()));
                 self.next_tok();
-                JSONValue::Bool(true)
+                self.expect_literal_rest(TRUE)?;
+                Ok(JSONValue::Bool(true))
             }
             'f' => {
-                assert!(biter.take(FALSE.len()).eq(FALSE.chars()));
                 self.next_tok();
-                JSONValue::Bool(false)
+                self.expect_literal_rest(FALSE)?;
+                Ok(JSONValue::Bool(false))
             }
             'n' => {
-                assert!(biter.take(NULL.len()).eq(NULL.chars()));
                 self.next_tok();
-                JSONValue::Null
+                self.expect_literal_rest(NULL)?;
+                Ok(JSONValue::Null)
             }
-            _ => panic!("Unexpected literal."),
+            _ => Err(self.error(ErrorCode::ExpectedSomeValue)),
         }
     }
 
-    pub fn parse(&mut self) -> JSONValue {
-        self.parse_any()
+    pub fn parse(&mut self) -> Result<JSONValue, ParserError> {
+        let value = self.parse_any()?;
+        if self.cur_tok != '\0' {
+            return Err(self.error(ErrorCode::TrailingCharacters));
+        }
+        Ok(value)
     }
 }
 
@@ -252,18 +477,124 @@ impl<'a> JSON<'a> {
 
 fn main() {
     let fname = env::args().nth(1).expect("Must pass filename to parse.");
-    let input = fs::read_to_string(fname).unwrap();
+    let input = fs::read_to_string(&fname).unwrap();
     let t = Instant::now();
-    let p = JSON::new(input.as_str()).parse();
+    let p = match JSON::new(input.as_str()).parse() {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!("Failed to parse {}: {}", fname, err);
+            std::process::exit(1);
+        }
+    };
     println!("time: {}ms", t.elapsed().as_micros() as f64 / 1000f64);
-    // p.from_path("asdf");
     if let JSONValue::Array(list) = &p {
         println!("Len: {}", list.len());
     }
-    if let JSONValue::Object(map) = &p {
-        let deps = map.get("dependencies").unwrap();
-        if let JSONValue::Object(map_) = &deps {
-            println!("Map len: {}", map_.len());
+    if let Some(deps) = p.select("$.dependencies").ok().and_then(|m| m.into_iter().next()) {
+        match deps.decode::<BTreeMap<String, String>>() {
+            Ok(parsed) => println!("Map len: {}", parsed.len()),
+            Err(err) => eprintln!("Failed to decode dependencies: {}", err),
+        }
+    }
+
+    let mut events = JsonEvents::new(input.as_str());
+    let mut event_count = 0usize;
+    let mut max_depth = 0usize;
+    while let Some(event) = events.next() {
+        if let JsonEvent::Error(err) = event {
+            eprintln!("Streaming parse failed: {}", err);
+            break;
         }
+        max_depth = max_depth.max(events.stack().len());
+        event_count += 1;
+    }
+    println!("Streamed {} events, max depth {}", event_count, max_depth);
+
+    println!("Compact size: {} bytes", p.to_string_compact().len());
+
+    let mut compact_bytes = Vec::new();
+    encode::to_io_writer_compact(&mut compact_bytes, &p).expect("writing to a Vec cannot fail");
+    println!("Compact size via io::Write: {} bytes", compact_bytes.len());
+
+    let mut pretty_bytes = Vec::new();
+    encode::to_io_writer_pretty(&mut pretty_bytes, &p, 2).expect("writing to a Vec cannot fail");
+    println!("Pretty size via io::Write: {} bytes", pretty_bytes.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<JSONValue, ParserError> {
+        JSON::new(input).parse()
+    }
+
+    #[test]
+    fn parses_negative_and_fractional_numbers() {
+        assert_eq!(parse("-1.5").unwrap().as_f64(), Some(-1.5));
+    }
+
+    #[test]
+    fn parses_exponents() {
+        assert_eq!(parse("6.02e23").unwrap().as_f64(), Some(6.02e23));
+        assert_eq!(parse("1E+2").unwrap().as_f64(), Some(100.0));
+    }
+
+    #[test]
+    fn rejects_overflowing_exponents_instead_of_yielding_infinity() {
+        assert_eq!(
+            parse("1e400"),
+            Err(ParserError::InvalidNumber("1e400".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_leading_zero() {
+        assert!(parse("01").is_err());
+    }
+
+    #[test]
+    fn preserves_literal_whitespace_inside_strings() {
+        let v = parse(r#""Likes long walks on the beach""#).unwrap();
+        assert_eq!(v.as_str(), Some("Likes long walks on the beach"));
+    }
+
+    #[test]
+    fn rejects_two_numbers_separated_only_by_whitespace() {
+        assert!(parse("[42 43]").is_err());
+    }
+
+    #[test]
+    fn parses_common_escape_sequences() {
+        let v = parse(r#""a\nb\tc\"d""#).unwrap();
+        assert_eq!(v.as_str(), Some("a\nb\tc\"d"));
+    }
+
+    #[test]
+    fn parses_unicode_escape() {
+        let v = parse("\"\\u00e9\"").unwrap();
+        assert_eq!(v.as_str(), Some("\u{e9}"));
+    }
+
+    #[test]
+    fn parses_surrogate_pair_escape() {
+        let v = parse("\"\\ud83d\\ude00\"").unwrap();
+        assert_eq!(v.as_str(), Some("\u{1F600}"));
+    }
+
+    #[test]
+    fn reports_accurate_column_after_a_literal() {
+        let err = parse("[true, @]").unwrap_err();
+        assert_eq!(
+            err,
+            ParserError::SyntaxError(ErrorCode::ExpectedSomeValue, 1, 8)
+        );
+    }
+
+    #[test]
+    fn array_uses_vec_for_o1_indexing() {
+        let v = parse("[1, 2, 3]").unwrap();
+        assert_eq!(v.get(1).and_then(JSONValue::as_f64), Some(2.0));
+        assert_eq!(v[1].as_f64(), Some(2.0));
     }
 }
@@ -0,0 +1,562 @@
+use crate::JSONValue;
+use std::fmt;
+
+/// An error produced while tokenizing or evaluating a JSONPath expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+    UnexpectedEnd,
+    UnexpectedChar(char, usize),
+    InvalidIndex(String),
+    UnknownOperator(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::UnexpectedEnd => write!(f, "unexpected end of path expression"),
+            PathError::UnexpectedChar(c, pos) => {
+                write!(f, "unexpected character '{}' at position {}", c, pos)
+            }
+            PathError::InvalidIndex(s) => write!(f, "invalid index \"{}\"", s),
+            PathError::UnknownOperator(s) => write!(f, "unknown filter operator \"{}\"", s),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterValue {
+    Number(f64),
+    String(String),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    field: String,
+    op: CompareOp,
+    value: FilterValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Root,
+    Child(String),
+    Wildcard,
+    DeepScan,
+    Index(isize),
+    Slice(Option<isize>, Option<isize>, Option<isize>),
+    Filter(Filter),
+}
+
+struct Tokenizer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Tokenizer {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, PathError> {
+        let mut tokens = Vec::new();
+        if self.eat('$') {
+            tokens.push(Token::Root);
+        }
+        while self.pos < self.chars.len() {
+            match self.peek().unwrap() {
+                '.' => {
+                    self.bump();
+                    if self.eat('.') {
+                        tokens.push(Token::DeepScan);
+                        if self.peek() == Some('*') {
+                            self.bump();
+                            tokens.push(Token::Wildcard);
+                        } else if self.peek() != Some('[') {
+                            tokens.push(Token::Child(self.read_ident()?));
+                        }
+                    } else if self.eat('*') {
+                        tokens.push(Token::Wildcard);
+                    } else {
+                        tokens.push(Token::Child(self.read_ident()?));
+                    }
+                }
+                '[' => {
+                    self.bump();
+                    tokens.push(self.read_bracket()?);
+                }
+                c => return Err(PathError::UnexpectedChar(c, self.pos)),
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn read_ident(&mut self) -> Result<String, PathError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+            self.bump();
+        }
+        if self.pos == start {
+            return Err(match self.peek() {
+                Some(c) => PathError::UnexpectedChar(c, self.pos),
+                None => PathError::UnexpectedEnd,
+            });
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn read_quoted(&mut self, quote: char) -> Result<String, PathError> {
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some(c) if c == quote => return Ok(s),
+                Some(c) => s.push(c),
+                None => return Err(PathError::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn read_bracket(&mut self) -> Result<Token, PathError> {
+        match self.peek() {
+            Some('*') => {
+                self.bump();
+                self.expect(']')?;
+                Ok(Token::Wildcard)
+            }
+            Some('\'') | Some('"') => {
+                let quote = self.bump().unwrap();
+                let key = self.read_quoted(quote)?;
+                self.expect(']')?;
+                Ok(Token::Child(key))
+            }
+            Some('?') => {
+                self.bump();
+                self.expect('(')?;
+                let filter = self.read_filter()?;
+                self.expect(')')?;
+                self.expect(']')?;
+                Ok(Token::Filter(filter))
+            }
+            _ => self.read_index_or_slice(),
+        }
+    }
+
+    fn read_signed_int(&mut self) -> Result<isize, PathError> {
+        let start = self.pos;
+        self.eat('-');
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.bump();
+        }
+        let s: String = self.chars[start..self.pos].iter().collect();
+        s.parse::<isize>()
+            .map_err(|_| PathError::InvalidIndex(s))
+    }
+
+    fn read_index_or_slice(&mut self) -> Result<Token, PathError> {
+        let read_part = |t: &mut Self| -> Result<Option<isize>, PathError> {
+            if matches!(t.peek(), Some(c) if c == '-' || c.is_ascii_digit()) {
+                Ok(Some(t.read_signed_int()?))
+            } else {
+                Ok(None)
+            }
+        };
+        let first = read_part(self)?;
+        if !self.eat(':') {
+            self.expect(']')?;
+            return Ok(Token::Index(first.ok_or(PathError::UnexpectedEnd)?));
+        }
+        let second = read_part(self)?;
+        let third = if self.eat(':') { read_part(self)? } else { None };
+        self.expect(']')?;
+        Ok(Token::Slice(first, second, third))
+    }
+
+    fn read_filter(&mut self) -> Result<Filter, PathError> {
+        self.expect('@')?;
+        self.expect('.')?;
+        let field = self.read_ident()?;
+        while self.peek() == Some(' ') {
+            self.bump();
+        }
+        let op = self.read_operator()?;
+        while self.peek() == Some(' ') {
+            self.bump();
+        }
+        let value = self.read_filter_value()?;
+        Ok(Filter { field, op, value })
+    }
+
+    fn read_operator(&mut self) -> Result<CompareOp, PathError> {
+        let two: String = self.chars[self.pos..(self.pos + 2).min(self.chars.len())]
+            .iter()
+            .collect();
+        let op = match two.as_str() {
+            "==" => Some((CompareOp::Eq, 2)),
+            "!=" => Some((CompareOp::Ne, 2)),
+            "<=" => Some((CompareOp::Le, 2)),
+            ">=" => Some((CompareOp::Ge, 2)),
+            _ => None,
+        };
+        if let Some((op, len)) = op {
+            self.pos += len;
+            return Ok(op);
+        }
+        match self.peek() {
+            Some('<') => {
+                self.bump();
+                Ok(CompareOp::Lt)
+            }
+            Some('>') => {
+                self.bump();
+                Ok(CompareOp::Gt)
+            }
+            Some(c) => Err(PathError::UnknownOperator(c.to_string())),
+            None => Err(PathError::UnexpectedEnd),
+        }
+    }
+
+    fn read_filter_value(&mut self) -> Result<FilterValue, PathError> {
+        match self.peek() {
+            Some('\'') | Some('"') => {
+                let quote = self.bump().unwrap();
+                Ok(FilterValue::String(self.read_quoted(quote)?))
+            }
+            Some(c) if c.is_ascii_digit() || c == '-' => {
+                let start = self.pos;
+                self.eat('-');
+                while matches!(self.peek(), Some(c) if c.is_ascii_digit() || c == '.') {
+                    self.bump();
+                }
+                let s: String = self.chars[start..self.pos].iter().collect();
+                s.parse::<f64>()
+                    .map(FilterValue::Number)
+                    .map_err(|_| PathError::InvalidIndex(s))
+            }
+            _ => {
+                let ident = self.read_ident()?;
+                match ident.as_str() {
+                    "true" => Ok(FilterValue::Bool(true)),
+                    "false" => Ok(FilterValue::Bool(false)),
+                    _ => Err(PathError::UnknownOperator(ident)),
+                }
+            }
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PathError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            match self.peek() {
+                Some(got) => Err(PathError::UnexpectedChar(got, self.pos)),
+                None => Err(PathError::UnexpectedEnd),
+            }
+        }
+    }
+}
+
+fn tokenize(path: &str) -> Result<Vec<Token>, PathError> {
+    Tokenizer::new(path).tokenize()
+}
+
+fn children_of(value: &JSONValue) -> Vec<&JSONValue> {
+    match value {
+        JSONValue::Object(map) => map.values().collect(),
+        JSONValue::Array(arr) => arr.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn normalize_index(i: isize, len: usize) -> Option<usize> {
+    let len = len as isize;
+    let idx = if i < 0 { len + i } else { i };
+    if idx < 0 || idx >= len {
+        None
+    } else {
+        Some(idx as usize)
+    }
+}
+
+fn slice_indices(
+    start: Option<isize>,
+    end: Option<isize>,
+    step: Option<isize>,
+    len: usize,
+) -> Vec<usize> {
+    let step = step.unwrap_or(1);
+    if step == 0 || len == 0 {
+        return Vec::new();
+    }
+    let len_i = len as isize;
+    let clamp = |i: isize| -> isize {
+        let i = if i < 0 { len_i + i } else { i };
+        i.clamp(0, len_i)
+    };
+    if step > 0 {
+        let start = start.map(clamp).unwrap_or(0);
+        let end = end.map(clamp).unwrap_or(len_i);
+        let mut out = Vec::new();
+        let mut i = start;
+        while i < end {
+            out.push(i as usize);
+            i += step;
+        }
+        out
+    } else {
+        let start = start.map(clamp).unwrap_or(len_i - 1).min(len_i - 1);
+        let end = end.map(clamp).unwrap_or(-1);
+        let mut out = Vec::new();
+        let mut i = start;
+        while i > end {
+            out.push(i as usize);
+            i += step;
+        }
+        out
+    }
+}
+
+fn filter_matches(value: &JSONValue, filter: &Filter) -> bool {
+    let field = match value {
+        JSONValue::Object(map) => map.get(&filter.field),
+        _ => None,
+    };
+    let field = match field {
+        Some(f) => f,
+        None => return false,
+    };
+    match (field, &filter.value) {
+        (JSONValue::Number(n), FilterValue::Number(v)) => compare(*n, *v, &filter.op),
+        (JSONValue::String(s), FilterValue::String(v)) => compare_str(s, v, &filter.op),
+        (JSONValue::Bool(b), FilterValue::Bool(v)) => match filter.op {
+            CompareOp::Eq => b == v,
+            CompareOp::Ne => b != v,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn compare(a: f64, b: f64, op: &CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => a == b,
+        CompareOp::Ne => a != b,
+        CompareOp::Lt => a < b,
+        CompareOp::Le => a <= b,
+        CompareOp::Gt => a > b,
+        CompareOp::Ge => a >= b,
+    }
+}
+
+fn compare_str(a: &str, b: &str, op: &CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => a == b,
+        CompareOp::Ne => a != b,
+        CompareOp::Lt => a < b,
+        CompareOp::Le => a <= b,
+        CompareOp::Gt => a > b,
+        CompareOp::Ge => a >= b,
+    }
+}
+
+fn walk<'v>(value: &'v JSONValue, tokens: &[Token]) -> Result<Vec<&'v JSONValue>, PathError> {
+    let Some((head, rest)) = tokens.split_first() else {
+        return Ok(vec![value]);
+    };
+    match head {
+        Token::Root => walk(value, rest),
+        Token::Child(key) => match value {
+            JSONValue::Object(map) => match map.get(key) {
+                Some(v) => walk(v, rest),
+                None => Ok(Vec::new()),
+            },
+            _ => Ok(Vec::new()),
+        },
+        Token::Wildcard => {
+            let mut out = Vec::new();
+            for child in children_of(value) {
+                out.extend(walk(child, rest)?);
+            }
+            Ok(out)
+        }
+        Token::DeepScan => walk_deep(value, rest),
+        Token::Index(i) => match value {
+            JSONValue::Array(arr) => match normalize_index(*i, arr.len()) {
+                Some(idx) => match arr.get(idx) {
+                    Some(v) => walk(v, rest),
+                    None => Ok(Vec::new()),
+                },
+                None => Ok(Vec::new()),
+            },
+            _ => Ok(Vec::new()),
+        },
+        Token::Slice(start, end, step) => match value {
+            JSONValue::Array(arr) => {
+                let mut out = Vec::new();
+                for idx in slice_indices(*start, *end, *step, arr.len()) {
+                    if let Some(v) = arr.get(idx) {
+                        out.extend(walk(v, rest)?);
+                    }
+                }
+                Ok(out)
+            }
+            _ => Ok(Vec::new()),
+        },
+        Token::Filter(filter) => match value {
+            JSONValue::Array(arr) => {
+                let mut out = Vec::new();
+                for item in arr.iter() {
+                    if filter_matches(item, filter) {
+                        out.extend(walk(item, rest)?);
+                    }
+                }
+                Ok(out)
+            }
+            JSONValue::Object(map) => {
+                let mut out = Vec::new();
+                for item in map.values() {
+                    if filter_matches(item, filter) {
+                        out.extend(walk(item, rest)?);
+                    }
+                }
+                Ok(out)
+            }
+            _ => Ok(Vec::new()),
+        },
+    }
+}
+
+fn walk_deep<'v>(value: &'v JSONValue, rest: &[Token]) -> Result<Vec<&'v JSONValue>, PathError> {
+    let mut out = walk(value, rest)?;
+    for child in children_of(value) {
+        out.extend(walk_deep(child, rest)?);
+    }
+    Ok(out)
+}
+
+/// Evaluates a JSONPath expression (`$.a.b`, `$.a[0]`, `$..c`, `$.a[?(@.age > 30)]`, ...)
+/// against `root`, returning borrowed references to every matching node.
+pub fn select<'v>(root: &'v JSONValue, path: &str) -> Result<Vec<&'v JSONValue>, PathError> {
+    let tokens = tokenize(path)?;
+    walk(root, &tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn arr(items: Vec<JSONValue>) -> JSONValue {
+        JSONValue::Array(items)
+    }
+
+    fn obj(pairs: Vec<(&str, JSONValue)>) -> JSONValue {
+        let mut map = BTreeMap::new();
+        for (k, v) in pairs {
+            map.insert(k.to_string(), v);
+        }
+        JSONValue::Object(map)
+    }
+
+    fn num(n: f64) -> JSONValue {
+        JSONValue::Number(n)
+    }
+
+    #[test]
+    fn child_access() {
+        let root = obj(vec![("a", obj(vec![("b", num(1.0))]))]);
+        let got = select(&root, "$.a.b").unwrap();
+        assert_eq!(got, vec![&num(1.0)]);
+    }
+
+    #[test]
+    fn negative_index() {
+        let root = arr(vec![num(1.0), num(2.0), num(3.0)]);
+        let got = select(&root, "$[-1]").unwrap();
+        assert_eq!(got, vec![&num(3.0)]);
+    }
+
+    #[test]
+    fn slice_with_step() {
+        let root = arr(vec![num(0.0), num(1.0), num(2.0), num(3.0), num(4.0)]);
+        let got = select(&root, "$[0:4:2]").unwrap();
+        assert_eq!(got, vec![&num(0.0), &num(2.0)]);
+    }
+
+    #[test]
+    fn wildcard_collects_all_children() {
+        let root = arr(vec![num(1.0), num(2.0)]);
+        let got = select(&root, "$[*]").unwrap();
+        assert_eq!(got, vec![&num(1.0), &num(2.0)]);
+    }
+
+    #[test]
+    fn recursive_descent_finds_nested_keys() {
+        let root = obj(vec![
+            ("a", obj(vec![("target", num(1.0))])),
+            ("b", arr(vec![obj(vec![("target", num(2.0))])])),
+        ]);
+        let mut got: Vec<f64> = select(&root, "$..target")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_f64().unwrap())
+            .collect();
+        got.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(got, vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn filter_predicate_matches_on_comparison() {
+        let root = arr(vec![
+            obj(vec![("age", num(30.0))]),
+            obj(vec![("age", num(40.0))]),
+        ]);
+        let got = select(&root, "$[?(@.age > 30)]").unwrap();
+        assert_eq!(got, vec![&obj(vec![("age", num(40.0))])]);
+    }
+
+    #[test]
+    fn out_of_range_index_yields_no_matches() {
+        let root = arr(vec![num(1.0)]);
+        assert_eq!(select(&root, "$[5]").unwrap(), Vec::<&JSONValue>::new());
+    }
+
+    #[test]
+    fn unterminated_bracket_is_an_error() {
+        let root = num(1.0);
+        assert_eq!(select(&root, "$[0"), Err(PathError::UnexpectedEnd));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::error::{ErrorCode, ParserError};
+use crate::{JSONValue, JSON};
+
+/// An event produced while pulling tokens off a [`JsonEvents`] stream.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    /// Precedes the value of an object member.
+    Key(String),
+    StringValue(String),
+    NumberValue(f64),
+    BoolValue(bool),
+    NullValue,
+    /// A malformed document; no further events follow.
+    Error(ParserError),
+}
+
+/// A single level of the streaming parser's current location: which array
+/// index or object key the cursor sits at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackElement {
+    Index(usize),
+    Key(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArrState {
+    /// No element parsed yet; a value or `]` may follow.
+    ExpectValueOrEnd,
+    /// An element was just parsed; a `,` or `]` may follow.
+    ExpectCommaOrEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ObjState {
+    /// No member parsed yet; a key or `}` may follow.
+    KeyOrEnd,
+    /// Just after a `,`; only a key may follow (no trailing comma).
+    Key,
+    Colon,
+    Value,
+    /// A member was just parsed; a `,` or `}` may follow.
+    CommaOrEnd,
+}
+
+enum Frame {
+    Array { state: ArrState, index: usize },
+    Object { state: ObjState, key: String },
+}
+
+#[derive(Clone, Copy)]
+enum FrameKind {
+    Array(ArrState),
+    Object(ObjState),
+}
+
+/// Pulls [`JsonEvent`]s out of a JSON document without ever materializing a
+/// full `JSONValue` tree, using an explicit state stack instead of
+/// recursion so memory use is `O(depth)`.
+pub struct JsonEvents<'a> {
+    json: JSON<'a>,
+    stack: Vec<Frame>,
+    top_started: bool,
+    done: bool,
+}
+
+impl<'a> JsonEvents<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            json: JSON::new(input),
+            stack: Vec::new(),
+            top_started: false,
+            done: false,
+        }
+    }
+
+    /// The current location, outermost frame first.
+    pub fn stack(&self) -> Vec<StackElement> {
+        self.stack
+            .iter()
+            .map(|frame| match frame {
+                Frame::Array { index, .. } => StackElement::Index(*index),
+                Frame::Object { key, .. } => StackElement::Key(key.clone()),
+            })
+            .collect()
+    }
+
+    fn frame_kind(&self) -> Option<FrameKind> {
+        match self.stack.last() {
+            Some(Frame::Array { state, .. }) => Some(FrameKind::Array(*state)),
+            Some(Frame::Object { state, .. }) => Some(FrameKind::Object(*state)),
+            None => None,
+        }
+    }
+
+    fn value_event(&mut self) -> Result<JsonEvent, ParserError> {
+        match self.json.cur_tok {
+            '{' => {
+                self.json.next_tok();
+                self.stack.push(Frame::Object {
+                    state: ObjState::KeyOrEnd,
+                    key: String::new(),
+                });
+                Ok(JsonEvent::ObjectStart)
+            }
+            '[' => {
+                self.json.next_tok();
+                self.stack.push(Frame::Array {
+                    state: ArrState::ExpectValueOrEnd,
+                    index: 0,
+                });
+                Ok(JsonEvent::ArrayStart)
+            }
+            '"' => match self.json.parse_string()? {
+                JSONValue::String(s) => Ok(JsonEvent::StringValue(s)),
+                _ => unreachable!(),
+            },
+            '0'..='9' | '-' => match self.json.parse_number()? {
+                JSONValue::Number(n) => Ok(JsonEvent::NumberValue(n)),
+                _ => unreachable!(),
+            },
+            't' | 'f' | 'n' => match self.json.parse_literal()? {
+                JSONValue::Bool(b) => Ok(JsonEvent::BoolValue(b)),
+                JSONValue::Null => Ok(JsonEvent::NullValue),
+                _ => unreachable!(),
+            },
+            '\0' => Err(ParserError::EOFWhileParsing),
+            _ => Err(self.json.error(ErrorCode::ExpectedSomeValue)),
+        }
+    }
+
+    fn parse_key(&mut self) -> Result<String, ParserError> {
+        if self.json.cur_tok != '"' {
+            return Err(self.json.error(ErrorCode::KeyMustBeAString));
+        }
+        match self.json.parse_string()? {
+            JSONValue::String(s) => Ok(s),
+            _ => unreachable!(),
+        }
+    }
+
+    fn fail(&mut self, err: ParserError) -> Option<JsonEvent> {
+        self.done = true;
+        Some(JsonEvent::Error(err))
+    }
+}
+
+impl<'a> Iterator for JsonEvents<'a> {
+    type Item = JsonEvent;
+
+    fn next(&mut self) -> Option<JsonEvent> {
+        loop {
+            if self.done {
+                return None;
+            }
+            let Some(kind) = self.frame_kind() else {
+                if !self.top_started {
+                    self.top_started = true;
+                    return match self.value_event() {
+                        Ok(ev) => Some(ev),
+                        Err(e) => self.fail(e),
+                    };
+                }
+                self.done = true;
+                return if self.json.cur_tok != '\0' {
+                    Some(JsonEvent::Error(
+                        self.json.error(ErrorCode::TrailingCharacters),
+                    ))
+                } else {
+                    None
+                };
+            };
+            match kind {
+                FrameKind::Array(ArrState::ExpectValueOrEnd) => {
+                    if self.json.cur_tok == ']' {
+                        self.json.next_tok();
+                        self.stack.pop();
+                        return Some(JsonEvent::ArrayEnd);
+                    }
+                    if let Some(Frame::Array { state, .. }) = self.stack.last_mut() {
+                        *state = ArrState::ExpectCommaOrEnd;
+                    }
+                    return match self.value_event() {
+                        Ok(ev) => Some(ev),
+                        Err(e) => self.fail(e),
+                    };
+                }
+                FrameKind::Array(ArrState::ExpectCommaOrEnd) => match self.json.cur_tok {
+                    ']' => {
+                        self.json.next_tok();
+                        self.stack.pop();
+                        return Some(JsonEvent::ArrayEnd);
+                    }
+                    ',' => {
+                        self.json.next_tok();
+                        if let Some(Frame::Array { index, .. }) = self.stack.last_mut() {
+                            *index += 1;
+                        }
+                        return match self.value_event() {
+                            Ok(ev) => Some(ev),
+                            Err(e) => self.fail(e),
+                        };
+                    }
+                    '\0' => return self.fail(ParserError::EOFWhileParsing),
+                    _ => return self.fail(self.json.error(ErrorCode::ExpectedListCommaOrEnd)),
+                },
+                FrameKind::Object(ObjState::KeyOrEnd) => {
+                    if self.json.cur_tok == '}' {
+                        self.json.next_tok();
+                        self.stack.pop();
+                        return Some(JsonEvent::ObjectEnd);
+                    }
+                    let key = match self.parse_key() {
+                        Ok(k) => k,
+                        Err(e) => return self.fail(e),
+                    };
+                    if let Some(Frame::Object { state, key: slot }) = self.stack.last_mut() {
+                        *state = ObjState::Colon;
+                        *slot = key.clone();
+                    }
+                    return Some(JsonEvent::Key(key));
+                }
+                FrameKind::Object(ObjState::Key) => {
+                    let key = match self.parse_key() {
+                        Ok(k) => k,
+                        Err(e) => return self.fail(e),
+                    };
+                    if let Some(Frame::Object { state, key: slot }) = self.stack.last_mut() {
+                        *state = ObjState::Colon;
+                        *slot = key.clone();
+                    }
+                    return Some(JsonEvent::Key(key));
+                }
+                FrameKind::Object(ObjState::Colon) => {
+                    if self.json.cur_tok != ':' {
+                        return self.fail(self.json.error(ErrorCode::ExpectedColon));
+                    }
+                    self.json.next_tok();
+                    if let Some(Frame::Object { state, .. }) = self.stack.last_mut() {
+                        *state = ObjState::Value;
+                    }
+                    continue;
+                }
+                FrameKind::Object(ObjState::Value) => {
+                    if let Some(Frame::Object { state, .. }) = self.stack.last_mut() {
+                        *state = ObjState::CommaOrEnd;
+                    }
+                    return match self.value_event() {
+                        Ok(ev) => Some(ev),
+                        Err(e) => self.fail(e),
+                    };
+                }
+                FrameKind::Object(ObjState::CommaOrEnd) => match self.json.cur_tok {
+                    '}' => {
+                        self.json.next_tok();
+                        self.stack.pop();
+                        return Some(JsonEvent::ObjectEnd);
+                    }
+                    ',' => {
+                        self.json.next_tok();
+                        if let Some(Frame::Object { state, .. }) = self.stack.last_mut() {
+                            *state = ObjState::Key;
+                        }
+                        continue;
+                    }
+                    '\0' => return self.fail(ParserError::EOFWhileParsing),
+                    _ => return self.fail(self.json.error(ErrorCode::ExpectedObjectCommaOrEnd)),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(input: &str) -> Vec<JsonEvent> {
+        JsonEvents::new(input).collect()
+    }
+
+    #[test]
+    fn empty_array() {
+        assert_eq!(events("[]"), vec![JsonEvent::ArrayStart, JsonEvent::ArrayEnd]);
+    }
+
+    #[test]
+    fn empty_object() {
+        assert_eq!(
+            events("{}"),
+            vec![JsonEvent::ObjectStart, JsonEvent::ObjectEnd]
+        );
+    }
+
+    #[test]
+    fn nested_containers() {
+        assert_eq!(
+            events(r#"{"a": [1, {"b": 2}]}"#),
+            vec![
+                JsonEvent::ObjectStart,
+                JsonEvent::Key("a".to_string()),
+                JsonEvent::ArrayStart,
+                JsonEvent::NumberValue(1.0),
+                JsonEvent::ObjectStart,
+                JsonEvent::Key("b".to_string()),
+                JsonEvent::NumberValue(2.0),
+                JsonEvent::ObjectEnd,
+                JsonEvent::ArrayEnd,
+                JsonEvent::ObjectEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_trailing_comma_in_array() {
+        let evs = events("[1,]");
+        assert!(matches!(evs.last(), Some(JsonEvent::Error(_))));
+    }
+
+    #[test]
+    fn rejects_trailing_comma_in_object() {
+        let evs = events(r#"{"a":1,}"#);
+        assert!(matches!(evs.last(), Some(JsonEvent::Error(_))));
+    }
+
+    #[test]
+    fn reports_eof_mid_value() {
+        let evs = events("[1,");
+        assert_eq!(
+            evs.last(),
+            Some(&JsonEvent::Error(ParserError::EOFWhileParsing))
+        );
+    }
+
+    #[test]
+    fn stack_tracks_array_index_and_object_key() {
+        let mut it = JsonEvents::new(r#"{"a": [1, 2]}"#);
+        assert_eq!(it.next(), Some(JsonEvent::ObjectStart));
+        assert_eq!(it.next(), Some(JsonEvent::Key("a".to_string())));
+        assert_eq!(it.next(), Some(JsonEvent::ArrayStart));
+        assert_eq!(it.next(), Some(JsonEvent::NumberValue(1.0)));
+        assert_eq!(
+            it.stack(),
+            vec![
+                StackElement::Key("a".to_string()),
+                StackElement::Index(0),
+            ]
+        );
+        assert_eq!(it.next(), Some(JsonEvent::NumberValue(2.0)));
+        assert_eq!(
+            it.stack(),
+            vec![
+                StackElement::Key("a".to_string()),
+                StackElement::Index(1),
+            ]
+        );
+    }
+}